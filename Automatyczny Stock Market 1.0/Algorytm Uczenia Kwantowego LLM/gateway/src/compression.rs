@@ -0,0 +1,177 @@
+//! `Accept-Encoding` negotiation for the proxy path: compress uncompressed
+//! upstream bodies for clients that accept it, and transparently decompress
+//! upstream bodies the client never asked for.
+
+use async_compression::tokio::bufread::{
+    BrotliDecoder, BrotliEncoder, DeflateDecoder, DeflateEncoder, GzipDecoder, GzipEncoder,
+};
+use axum::body::{Body, Bytes};
+use axum::http::{HeaderMap, HeaderValue};
+use futures_util::{Stream, TryStreamExt};
+use hyper::header;
+use tokio_util::io::{ReaderStream, StreamReader};
+
+/// Encodings we know how to produce/consume, ordered by preference
+/// (br > gzip > deflate) when the client accepts more than one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    Brotli,
+    Gzip,
+    Deflate,
+}
+
+impl Encoding {
+    fn token(self) -> &'static str {
+        match self {
+            Encoding::Brotli => "br",
+            Encoding::Gzip => "gzip",
+            Encoding::Deflate => "deflate",
+        }
+    }
+
+    fn from_token(token: &str) -> Option<Self> {
+        match token {
+            "br" => Some(Encoding::Brotli),
+            "gzip" | "x-gzip" => Some(Encoding::Gzip),
+            "deflate" => Some(Encoding::Deflate),
+            _ => None,
+        }
+    }
+}
+
+/// Content types that are already compressed (or gain nothing from it);
+/// re-compressing these just burns CPU for no bandwidth savings.
+fn is_incompressible(content_type: &str) -> bool {
+    let ct = content_type.split(';').next().unwrap_or("").trim();
+    matches!(
+        ct,
+        "image/jpeg"
+            | "image/png"
+            | "image/webp"
+            | "image/gif"
+            | "video/mp4"
+            | "video/webm"
+            | "audio/mpeg"
+            | "application/zip"
+            | "application/gzip"
+            | "application/x-gzip"
+    )
+}
+
+/// Parses an `Accept-Encoding` header and picks the best supported encoding,
+/// honoring `q=0` exclusions. Returns `None` if the client accepts only
+/// `identity` or encodings we don't support.
+pub fn negotiate(headers: &HeaderMap) -> Option<Encoding> {
+    let raw = headers.get(header::ACCEPT_ENCODING)?.to_str().ok()?;
+
+    let mut best: Option<(Encoding, f32)> = None;
+    for part in raw.split(',') {
+        let part = part.trim();
+        if part.is_empty() {
+            continue;
+        }
+        let mut segments = part.split(';');
+        let token = segments.next().unwrap_or("").trim();
+        let Some(enc) = Encoding::from_token(token) else {
+            continue;
+        };
+        let q = segments
+            .find_map(|p| p.trim().strip_prefix("q="))
+            .and_then(|v| v.parse::<f32>().ok())
+            .unwrap_or(1.0);
+        if q <= 0.0 {
+            continue;
+        }
+        let rank = match enc {
+            Encoding::Brotli => 2,
+            Encoding::Gzip => 1,
+            Encoding::Deflate => 0,
+        };
+        let better = match best {
+            None => true,
+            Some((cur, cur_q)) => {
+                let cur_rank = match cur {
+                    Encoding::Brotli => 2,
+                    Encoding::Gzip => 1,
+                    Encoding::Deflate => 0,
+                };
+                q > cur_q || (q == cur_q && rank > cur_rank)
+            }
+        };
+        if better {
+            best = Some((enc, q));
+        }
+    }
+    best.map(|(enc, _)| enc)
+}
+
+/// Returns the upstream response's `Content-Encoding`, if any, as an
+/// `Encoding` we know how to decode.
+pub fn response_encoding(headers: &HeaderMap) -> Option<Encoding> {
+    let raw = headers.get(header::CONTENT_ENCODING)?.to_str().ok()?;
+    Encoding::from_token(raw.trim())
+}
+
+/// True if the client's `Accept-Encoding` header explicitly allows
+/// `encoding` with a non-zero quality value.
+pub fn client_accepts(headers: &HeaderMap, encoding: Encoding) -> bool {
+    let Some(raw) = headers.get(header::ACCEPT_ENCODING).and_then(|v| v.to_str().ok()) else {
+        return false;
+    };
+    raw.split(',').any(|part| {
+        let part = part.trim();
+        let mut segments = part.split(';');
+        let Some(enc) = segments.next().map(str::trim).and_then(Encoding::from_token) else {
+            return false;
+        };
+        if enc != encoding {
+            return false;
+        }
+        let q = segments
+            .find_map(|p| p.trim().strip_prefix("q="))
+            .and_then(|v| v.parse::<f32>().ok())
+            .unwrap_or(1.0);
+        q > 0.0
+    })
+}
+
+fn body_to_async_read<S, E>(stream: S) -> impl tokio::io::AsyncBufRead
+where
+    S: Stream<Item = Result<Bytes, E>>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    StreamReader::new(stream.map_err(std::io::Error::other))
+}
+
+/// Re-encodes a response body stream with `encoding`, streaming chunk by
+/// chunk rather than buffering the whole payload.
+pub fn encode_body(body: Body, encoding: Encoding) -> Body {
+    let reader = body_to_async_read(body.into_data_stream());
+    match encoding {
+        Encoding::Brotli => Body::from_stream(ReaderStream::new(BrotliEncoder::new(reader))),
+        Encoding::Gzip => Body::from_stream(ReaderStream::new(GzipEncoder::new(reader))),
+        Encoding::Deflate => Body::from_stream(ReaderStream::new(DeflateEncoder::new(reader))),
+    }
+}
+
+/// Decodes a response body stream that arrived with `encoding` but that the
+/// client never asked for.
+pub fn decode_body(body: Body, encoding: Encoding) -> Body {
+    let reader = body_to_async_read(body.into_data_stream());
+    match encoding {
+        Encoding::Brotli => Body::from_stream(ReaderStream::new(BrotliDecoder::new(reader))),
+        Encoding::Gzip => Body::from_stream(ReaderStream::new(GzipDecoder::new(reader))),
+        Encoding::Deflate => Body::from_stream(ReaderStream::new(DeflateDecoder::new(reader))),
+    }
+}
+
+pub fn encoding_header_value(encoding: Encoding) -> HeaderValue {
+    HeaderValue::from_static(encoding.token())
+}
+
+pub fn should_compress(content_type: Option<&HeaderValue>) -> bool {
+    match content_type.and_then(|v| v.to_str().ok()) {
+        Some(ct) => !is_incompressible(ct),
+        None => true,
+    }
+}
@@ -0,0 +1,104 @@
+//! CORS configuration sourced from the environment instead of hardcoded
+//! origins, so allowed domains can change without rebuilding the gateway.
+
+use std::env;
+use std::time::Duration;
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+use axum::http::{HeaderName, HeaderValue, Method};
+use hyper::header;
+
+#[derive(Clone, Debug)]
+pub struct CorsConfig {
+    pub allowed_origins: Vec<String>,
+    pub allowed_headers: Vec<String>,
+    pub allow_credentials: bool,
+    pub max_age: Duration,
+}
+
+impl CorsConfig {
+    /// Reads `CORS_ALLOWED_ORIGINS`, `CORS_ALLOWED_HEADERS`,
+    /// `CORS_ALLOW_CREDENTIALS`, and `CORS_MAX_AGE` from the environment,
+    /// falling back to the gateway's historical origins, headers and
+    /// credentials setting, and to a 600-second max age.
+    pub fn from_env() -> Self {
+        let allowed_origins = env::var("CORS_ALLOWED_ORIGINS")
+            .unwrap_or_else(|_| "https://ase-bot.live,https://www.ase-bot.live".to_string())
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        let allowed_headers = env::var("CORS_ALLOWED_HEADERS")
+            .unwrap_or_else(|_| "accept,authorization,content-type,x-csrf-token".to_string())
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        let allow_credentials = env::var("CORS_ALLOW_CREDENTIALS")
+            .map(|v| v == "true" || v == "1")
+            .unwrap_or(false);
+
+        let max_age = env::var("CORS_MAX_AGE")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(Duration::from_secs(600));
+
+        Self { allowed_origins, allowed_headers, allow_credentials, max_age }
+    }
+
+    /// True if `origin` matches an entry verbatim, or matches a `*.example.com`
+    /// wildcard entry against `example.com` itself or any subdomain of it.
+    fn origin_allowed(&self, origin: &str) -> bool {
+        self.allowed_origins.iter().any(|allowed| {
+            if let Some(suffix) = allowed.strip_prefix("*.") {
+                origin
+                    .strip_prefix("https://")
+                    .or_else(|| origin.strip_prefix("http://"))
+                    .map(|host| host == suffix || host.ends_with(&format!(".{suffix}")))
+                    .unwrap_or(false)
+            } else {
+                origin == allowed
+            }
+        })
+    }
+
+    pub fn build_layer(&self) -> CorsLayer {
+        let config = self.clone();
+        let allow_origin = AllowOrigin::predicate(move |origin: &HeaderValue, _| {
+            origin
+                .to_str()
+                .map(|o| config.origin_allowed(o))
+                .unwrap_or(false)
+        });
+
+        let allow_headers: Vec<HeaderName> = self
+            .allowed_headers
+            .iter()
+            .filter_map(|h| HeaderName::try_from(h.as_str()).ok())
+            .collect();
+
+        let mut layer = CorsLayer::new()
+            .allow_origin(allow_origin)
+            .allow_methods(vec![
+                Method::GET,
+                Method::POST,
+                Method::PUT,
+                Method::PATCH,
+                Method::DELETE,
+                Method::OPTIONS,
+            ])
+            .allow_headers(allow_headers)
+            .allow_credentials(self.allow_credentials)
+            .max_age(self.max_age);
+
+        if self.allow_credentials {
+            layer = layer.expose_headers(vec![header::SET_COOKIE]);
+        }
+        layer
+    }
+}
@@ -0,0 +1,336 @@
+use anyhow::Result;
+use axum::{
+    body::{Body, Bytes},
+    extract::{ConnectInfo, DefaultBodyLimit, Json, State},
+    http::{HeaderMap, Method, StatusCode, Uri},
+    response::IntoResponse,
+    routing::{any, get, post},
+    Router,
+};
+use futures_util::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use std::{env, net::SocketAddr, sync::Arc};
+use tower_http::trace::TraceLayer;
+use hyper::header::{self, HeaderValue};
+
+pub mod auth;
+pub mod compression;
+pub mod cors;
+pub mod proxy_headers;
+pub mod resilience;
+
+use auth::ApiAuth;
+use cors::CorsConfig;
+use resilience::{CircuitBreaker, DispatchOutcome, RetryConfig};
+
+/// Hard ceiling on proxied request bodies, enforced while streaming them
+/// upstream. Upstream response bodies are relayed without a size limit.
+const DEFAULT_MAX_BODY_BYTES: u64 = 10 * 1024 * 1024;
+/// Default per-request deadline before a slow backend gets a 408 instead of
+/// hanging the client indefinitely.
+const DEFAULT_REQUEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(15);
+
+#[derive(Clone)]
+pub struct AppState {
+    pub backend_base: String,
+    pub client: reqwest::Client,
+    pub max_body_bytes: u64,
+    pub auth: Arc<dyn ApiAuth>,
+    pub retry: RetryConfig,
+    pub request_deadline: std::time::Duration,
+    pub circuit_breaker: Arc<CircuitBreaker>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct LoginPayload {
+    email: String,
+    password: String,
+}
+
+pub async fn run() -> Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .init();
+
+    let backend_base = env::var("BACKEND_BASE").unwrap_or_else(|_| "http://127.0.0.1:8009".to_string());
+    let listen_addr: SocketAddr = env::var("GATEWAY_ADDR")
+        .unwrap_or_else(|_| "0.0.0.0:8080".into())
+        .parse()
+        .expect("invalid GATEWAY_ADDR");
+    let max_body_bytes = env::var("MAX_BODY_BYTES")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_MAX_BODY_BYTES);
+    let request_deadline = env::var("REQUEST_TIMEOUT_MS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .map(std::time::Duration::from_millis)
+        .unwrap_or(DEFAULT_REQUEST_TIMEOUT);
+
+    let state = Arc::new(AppState {
+        backend_base,
+        client: reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .pool_idle_timeout(Some(std::time::Duration::from_secs(10)))
+            .build()?,
+        max_body_bytes,
+        auth: auth::from_env(),
+        retry: RetryConfig::from_env(),
+        request_deadline,
+        circuit_breaker: Arc::new(CircuitBreaker::from_env()),
+    });
+
+    let app = build_app(state, &CorsConfig::from_env());
+
+    tracing::info!("listening on {}", listen_addr);
+    let listener = tokio::net::TcpListener::bind(listen_addr).await?;
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Assembles the router so tests can build the exact same app against a
+/// chosen `CorsConfig` without going through `run`.
+pub fn build_app(state: Arc<AppState>, cors_config: &CorsConfig) -> Router {
+    Router::new()
+        .route("/health", get(health))
+        .route("/api/login", post(api_login))
+        .route("/api/analysis/market", post(api_analysis_market))
+        .route("/", get(proxy_root_get)) // Explicitly handle root GET
+        .route("/*path", any(proxy_all)) // Fallback route
+        .layer(cors_config.build_layer())
+        .layer(DefaultBodyLimit::max(state.max_body_bytes as usize))
+        .layer(TraceLayer::new_for_http())
+        .with_state(state)
+}
+
+async fn health() -> impl IntoResponse {
+    (StatusCode::OK, "{\"status\":\"ok\"}")
+}
+
+async fn api_login(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(payload): Json<LoginPayload>,
+) -> impl IntoResponse {
+    // Exempt from `ApiAuth`: this is the endpoint that issues credentials in
+    // the first place, so there's nothing yet to authenticate against.
+    let url = format!("{}/api/login", state.backend_base);
+    // POST is not idempotent, so a single attempt; the deadline/circuit
+    // breaker still apply.
+    let outcome = resilience::dispatch(
+        &state.circuit_breaker,
+        &state.retry,
+        state.request_deadline,
+        false,
+        || state.client.post(&url).json(&payload),
+    )
+    .await;
+    stream_upstream_response(outcome, &headers).await
+}
+
+async fn api_analysis_market(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    body: Body,
+) -> impl IntoResponse {
+    if let Err(status) = state
+        .auth
+        .authenticate(&headers, &Method::POST, "/api/analysis/market")
+        .await
+    {
+        return status.into_response();
+    }
+
+    let url = format!("{}/api/analysis/market", state.backend_base);
+    let content_type = headers.get("content-type").cloned();
+    let mut body_once = Some(limited_request_body(body, state.max_body_bytes));
+
+    // POST with a streamed body: one attempt only, we can't replay the stream.
+    let outcome = resilience::dispatch(
+        &state.circuit_breaker,
+        &state.retry,
+        state.request_deadline,
+        false,
+        || {
+            let mut req = state.client.post(&url);
+            if let Some(ct) = &content_type {
+                req = req.header("content-type", ct);
+            }
+            match body_once.take() {
+                Some(b) => req.body(b),
+                None => req,
+            }
+        },
+    )
+    .await;
+    stream_upstream_response(outcome, &headers).await
+}
+
+/// Wraps an inbound axum body in a streaming reqwest body, enforcing `limit`
+/// without ever collecting the whole payload into memory.
+fn limited_request_body(body: Body, limit: u64) -> reqwest::Body {
+    let mut remaining = limit;
+    let stream = body.into_data_stream().and_then(move |chunk: Bytes| {
+        let ok = if (chunk.len() as u64) <= remaining {
+            remaining -= chunk.len() as u64;
+            true
+        } else {
+            false
+        };
+        async move {
+            if ok {
+                Ok(chunk)
+            } else {
+                Err(axum::Error::new(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "request body exceeds MAX_BODY_BYTES",
+                )))
+            }
+        }
+    });
+    reqwest::Body::wrap_stream(stream)
+}
+
+async fn proxy_all(
+    State(state): State<Arc<AppState>>,
+    method: Method,
+    uri: Uri,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
+    headers: HeaderMap,
+    body: Body,
+) -> impl IntoResponse {
+    let auth_context = match state.auth.authenticate(&headers, &method, uri.path()).await {
+        Ok(ctx) => ctx,
+        Err(status) => return status.into_response(),
+    };
+
+    let mut url = format!("{}{}", state.backend_base, uri.path());
+    if let Some(q) = uri.query() { url.push('?'); url.push_str(q); }
+
+    let build_base_request = |m: &Method| match *m {
+        Method::GET => state.client.get(&url),
+        Method::POST => state.client.post(&url),
+        Method::PUT => state.client.put(&url),
+        Method::PATCH => state.client.patch(&url),
+        Method::DELETE => state.client.delete(&url),
+        _ => state.client.request(m.clone(), &url),
+    };
+
+    let peer_ip = connect_info
+        .map(|ConnectInfo(addr)| addr.ip())
+        .unwrap_or_else(|| std::net::IpAddr::V4(std::net::Ipv4Addr::UNSPECIFIED));
+    let mut forwarded_headers = proxy_headers::build_forwarded_request_headers(&headers, peer_ip);
+    // Inject the verified subject; never trust a client-supplied copy of this header.
+    if let Ok(hv) = HeaderValue::from_str(&auth_context.subject) {
+        forwarded_headers.insert("x-auth-subject", hv);
+    }
+
+    // Safe to retry only when the method is idempotent AND there's no body
+    // to replay; otherwise a single attempt, same as a non-idempotent method.
+    let retryable = resilience::is_idempotent_method(&method) && resilience::body_is_empty(&headers);
+    let mut body_once = if retryable { None } else { Some(limited_request_body(body, state.max_body_bytes)) };
+
+    let outcome = resilience::dispatch(
+        &state.circuit_breaker,
+        &state.retry,
+        state.request_deadline,
+        retryable,
+        || {
+            let mut req = build_base_request(&method).headers(forwarded_headers.clone());
+            if method != Method::GET {
+                if let Some(b) = body_once.take() {
+                    req = req.body(b);
+                }
+            }
+            req
+        },
+    )
+    .await;
+
+    stream_upstream_response(outcome, &headers).await.into_response()
+}
+
+/// Converts a resilience [`DispatchOutcome`] into an axum response, relaying
+/// a successful body as a stream and renegotiating its `Content-Encoding`
+/// against what `client_headers` advertises in `Accept-Encoding`.
+async fn stream_upstream_response(
+    outcome: DispatchOutcome,
+    client_headers: &HeaderMap,
+) -> axum::response::Response {
+    match outcome {
+        DispatchOutcome::Response(r) => {
+            let status = StatusCode::from_u16(r.status().as_u16()).unwrap_or(StatusCode::BAD_GATEWAY);
+            let mut upstream_headers = r.headers().clone();
+            proxy_headers::sanitize_response_headers(&mut upstream_headers);
+            let content_type_header = upstream_headers.get(header::CONTENT_TYPE).cloned();
+            let upstream_encoding = compression::response_encoding(&upstream_headers);
+
+            let mut body = Body::from_stream(r.bytes_stream());
+            let mut content_encoding: Option<HeaderValue> = None;
+
+            if let Some(enc) = upstream_encoding {
+                if compression::client_accepts(client_headers, enc) {
+                    content_encoding = Some(compression::encoding_header_value(enc));
+                } else {
+                    // Client never asked for this encoding: decode it before relaying.
+                    body = compression::decode_body(body, enc);
+                }
+            } else if let Some(raw) = upstream_headers.get(header::CONTENT_ENCODING) {
+                // An encoding we can't decode: relay body and header untouched.
+                content_encoding = Some(raw.clone());
+            }
+
+            if content_encoding.is_none() && compression::should_compress(content_type_header.as_ref()) {
+                if let Some(best) = compression::negotiate(client_headers) {
+                    body = compression::encode_body(body, best);
+                    content_encoding = Some(compression::encoding_header_value(best));
+                }
+            }
+
+            let mut response = (status, body).into_response();
+            // Relay every upstream header except the ones we recompute ourselves
+            // below, preserving repeated headers like multiple Set-Cookie values.
+            for (name, value) in upstream_headers.iter() {
+                if name == header::CONTENT_ENCODING || name == header::CONTENT_LENGTH {
+                    continue;
+                }
+                response.headers_mut().append(name.clone(), value.clone());
+            }
+            if let Some(ce) = content_encoding {
+                response.headers_mut().insert(header::CONTENT_ENCODING, ce);
+            }
+            // The body length changed (re-encoded, decoded, or is now a stream of
+            // unknown size), so any upstream Content-Length would be wrong.
+            response.headers_mut().remove(header::CONTENT_LENGTH);
+            response
+        }
+        DispatchOutcome::Error(e) => {
+            let body = format!("{{\"error\":\"backend_error\",\"message\":\"{:?}\"}}", e);
+            (StatusCode::BAD_GATEWAY, body).into_response()
+        }
+        DispatchOutcome::DeadlineExceeded => {
+            let body = "{\"error\":\"upstream_timeout\"}";
+            (StatusCode::REQUEST_TIMEOUT, body).into_response()
+        }
+        DispatchOutcome::CircuitOpen => {
+            let body = "{\"error\":\"backend_unavailable\"}";
+            (StatusCode::SERVICE_UNAVAILABLE, body).into_response()
+        }
+    }
+}
+
+// Explicit GET handler for the root path to ensure the login page loads.
+async fn proxy_root_get(
+    State(state): State<Arc<AppState>>,
+    method: Method,
+    uri: Uri,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    // We can reuse the `proxy_all` logic by passing an empty body
+    proxy_all(State(state), method, uri, connect_info, headers, Body::empty()).await
+}
@@ -0,0 +1,107 @@
+//! Correct reverse-proxy header handling: strip hop-by-hop headers in both
+//! directions (RFC 7230 §6.1) and synthesize the standard forwarding
+//! headers (`X-Forwarded-For`/`Proto`/`Host`, `Via`) instead of relaying or
+//! dropping headers via a fixed allowlist.
+
+use axum::http::{HeaderMap, HeaderName, HeaderValue};
+use hyper::header;
+use std::net::IpAddr;
+
+const VIA_TOKEN: &str = "1.1 ase-bot-gateway";
+
+fn keep_alive() -> HeaderName {
+    HeaderName::from_static("keep-alive")
+}
+
+fn te() -> HeaderName {
+    HeaderName::from_static("te")
+}
+
+fn trailer() -> HeaderName {
+    HeaderName::from_static("trailer")
+}
+
+/// Header names that must never cross a hop: the fixed RFC 7230 set, plus
+/// whatever the `Connection` header itself names.
+fn hop_by_hop_names(headers: &HeaderMap) -> Vec<HeaderName> {
+    let mut names = vec![
+        header::CONNECTION,
+        keep_alive(),
+        header::TRANSFER_ENCODING,
+        header::UPGRADE,
+        header::PROXY_AUTHENTICATE,
+        header::PROXY_AUTHORIZATION,
+        te(),
+        trailer(),
+    ];
+    if let Some(connection) = headers.get(header::CONNECTION).and_then(|v| v.to_str().ok()) {
+        names.extend(
+            connection
+                .split(',')
+                .filter_map(|tok| HeaderName::try_from(tok.trim()).ok()),
+        );
+    }
+    names
+}
+
+pub fn strip_hop_by_hop(headers: &mut HeaderMap) {
+    for name in hop_by_hop_names(headers) {
+        headers.remove(name);
+    }
+}
+
+fn append_comma(headers: &mut HeaderMap, name: HeaderName, value: &str) {
+    let merged = match headers.get(&name).and_then(|v| v.to_str().ok()) {
+        Some(existing) if !existing.is_empty() => format!("{existing}, {value}"),
+        _ => value.to_string(),
+    };
+    if let Ok(hv) = HeaderValue::from_str(&merged) {
+        headers.insert(name, hv);
+    }
+}
+
+/// Builds the header set to send upstream: a copy of the inbound headers
+/// with hop-by-hop headers stripped, `X-Forwarded-For` appended with
+/// `peer_ip`, `X-Forwarded-Proto`/`X-Forwarded-Host` set, and `Via`
+/// appended. Any client-supplied `x-auth-subject` is dropped so
+/// [`crate::auth`] remains the only source of truth for it. The client's
+/// `Host` is preserved as `X-Forwarded-Host` but not relayed verbatim as
+/// `Host` itself — reqwest sets that from the request URL, so the backend
+/// sees its own authority rather than a client-controlled one.
+pub fn build_forwarded_request_headers(inbound: &HeaderMap, peer_ip: IpAddr) -> HeaderMap {
+    let mut out = inbound.clone();
+    strip_hop_by_hop(&mut out);
+    out.remove("x-auth-subject");
+
+    let original_host = out.remove(header::HOST);
+
+    append_comma(
+        &mut out,
+        HeaderName::from_static("x-forwarded-for"),
+        &peer_ip.to_string(),
+    );
+
+    let proto_name = HeaderName::from_static("x-forwarded-proto");
+    let proto = out
+        .get(&proto_name)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string)
+        .unwrap_or_else(|| "http".to_string());
+    if let Ok(hv) = HeaderValue::from_str(&proto) {
+        out.insert(proto_name, hv);
+    }
+
+    if let Some(host) = original_host {
+        out.insert(HeaderName::from_static("x-forwarded-host"), host);
+    }
+
+    append_comma(&mut out, header::VIA, VIA_TOKEN);
+
+    out
+}
+
+/// Strips hop-by-hop headers from an upstream response before relaying the
+/// rest back to the client.
+pub fn sanitize_response_headers(headers: &mut HeaderMap) {
+    strip_hop_by_hop(headers);
+}
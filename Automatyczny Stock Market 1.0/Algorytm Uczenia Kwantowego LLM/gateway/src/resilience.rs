@@ -0,0 +1,202 @@
+//! Backend resilience for the proxy path: bounded retries with backoff and
+//! jitter for safe, bodyless requests; a per-request deadline that surfaces
+//! as `408 Request Timeout` instead of hanging; and a circuit breaker that
+//! short-circuits a stalled or flapping backend with `503`.
+
+use axum::http::{HeaderMap, Method};
+use rand::Rng;
+use std::env;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Copy, Debug)]
+pub struct RetryConfig {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+}
+
+impl RetryConfig {
+    pub fn from_env() -> Self {
+        let max_retries = env::var("RETRY_MAX").ok().and_then(|v| v.parse().ok()).unwrap_or(2);
+        let base_delay_ms = env::var("RETRY_BASE_MS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(100);
+        Self { max_retries, base_delay: Duration::from_millis(base_delay_ms) }
+    }
+
+    /// Exponential backoff from `base_delay`, plus up to 50% jitter so a
+    /// burst of retries doesn't all land on the backend at once.
+    fn backoff(&self, attempt: u32) -> Duration {
+        let exp = self.base_delay.saturating_mul(1u32 << attempt.min(10));
+        let jitter_ms = rand::thread_rng().gen_range(0..=(exp.as_millis() as u64 / 2 + 1));
+        exp + Duration::from_millis(jitter_ms)
+    }
+}
+
+/// Methods that are safe to retry per HTTP semantics (GET, PUT, DELETE,
+/// HEAD, OPTIONS). The caller must still confirm the request has no body to
+/// replay before actually retrying it.
+pub fn is_idempotent_method(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::PUT | Method::DELETE | Method::HEAD | Method::OPTIONS
+    )
+}
+
+/// True if the inbound request carries no body, based on `Content-Length`/
+/// `Transfer-Encoding`. We never replay a consumed streaming body, so only
+/// bodyless requests are retry candidates regardless of method.
+pub fn body_is_empty(headers: &HeaderMap) -> bool {
+    match headers
+        .get(axum::http::header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+    {
+        Some("0") => true,
+        Some(_) => false,
+        None => !headers.contains_key(axum::http::header::TRANSFER_ENCODING),
+    }
+}
+
+struct BreakerState {
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    probing: bool,
+}
+
+/// A simple per-backend circuit breaker: after `failure_threshold`
+/// consecutive failures it opens and rejects everything with 503 for
+/// `cooldown`, then lets exactly one half-open probe through to decide
+/// whether to close again.
+pub struct CircuitBreaker {
+    failure_threshold: u32,
+    cooldown: Duration,
+    state: Mutex<BreakerState>,
+}
+
+impl CircuitBreaker {
+    pub fn from_env() -> Self {
+        let failure_threshold = env::var("CB_FAILURE_THRESHOLD")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(5);
+        let cooldown_ms = env::var("CB_COOLDOWN_MS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(10_000);
+        Self::new(failure_threshold, Duration::from_millis(cooldown_ms))
+    }
+
+    /// Builds a breaker directly from its parameters, bypassing the
+    /// environment entirely. Exists mainly so tests can pin a threshold and
+    /// cooldown without mutating process-global env vars.
+    pub fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            failure_threshold,
+            cooldown,
+            state: Mutex::new(BreakerState {
+                consecutive_failures: 0,
+                opened_at: None,
+                probing: false,
+            }),
+        }
+    }
+
+    /// `Err` means "open, reject with 503". `Ok(true)` means this call is the
+    /// single half-open probe; `Ok(false)` means the breaker is closed.
+    fn admit(&self) -> Result<bool, ()> {
+        let mut state = self.state.lock().unwrap();
+        match state.opened_at {
+            None => Ok(false),
+            Some(_) if state.probing => Err(()),
+            Some(opened_at) if opened_at.elapsed() < self.cooldown => Err(()),
+            Some(_) => {
+                state.probing = true;
+                Ok(true)
+            }
+        }
+    }
+
+    fn record_success(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.consecutive_failures = 0;
+        state.opened_at = None;
+        state.probing = false;
+    }
+
+    fn record_failure(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.probing = false;
+        state.consecutive_failures += 1;
+        if state.consecutive_failures >= self.failure_threshold {
+            state.opened_at = Some(Instant::now());
+        }
+    }
+}
+
+/// Outcome of [`dispatch`]: an upstream response (possibly a 5xx after
+/// retries were exhausted), the transport error of the last attempt, or one
+/// of the resilience short-circuits.
+pub enum DispatchOutcome {
+    Response(reqwest::Response),
+    Error(reqwest::Error),
+    DeadlineExceeded,
+    CircuitOpen,
+}
+
+/// Sends a request built by `make_request`, retrying on connection/5xx
+/// failures when `retryable` is true, bounding each attempt by `deadline`,
+/// and consulting `breaker` before making any attempt at all.
+pub async fn dispatch<F>(
+    breaker: &CircuitBreaker,
+    retry: &RetryConfig,
+    deadline: Duration,
+    retryable: bool,
+    mut make_request: F,
+) -> DispatchOutcome
+where
+    F: FnMut() -> reqwest::RequestBuilder,
+{
+    let probe = match breaker.admit() {
+        Err(()) => return DispatchOutcome::CircuitOpen,
+        Ok(probe) => probe,
+    };
+
+    let attempts = if retryable && !probe { retry.max_retries + 1 } else { 1 };
+    let mut last_failure = None;
+
+    for attempt in 0..attempts {
+        if attempt > 0 {
+            tokio::time::sleep(retry.backoff(attempt - 1)).await;
+        }
+
+        match tokio::time::timeout(deadline, make_request().send()).await {
+            Ok(Ok(resp)) if resp.status().is_server_error() => {
+                breaker.record_failure();
+                let is_last = attempt + 1 == attempts;
+                last_failure = Some(DispatchOutcome::Response(resp));
+                if is_last {
+                    break;
+                }
+            }
+            Ok(Ok(resp)) => {
+                breaker.record_success();
+                return DispatchOutcome::Response(resp);
+            }
+            Ok(Err(e)) => {
+                breaker.record_failure();
+                let is_last = attempt + 1 == attempts;
+                last_failure = Some(DispatchOutcome::Error(e));
+                if is_last {
+                    break;
+                }
+            }
+            Err(_elapsed) => {
+                breaker.record_failure();
+                return DispatchOutcome::DeadlineExceeded;
+            }
+        }
+    }
+
+    last_failure.unwrap_or(DispatchOutcome::DeadlineExceeded)
+}
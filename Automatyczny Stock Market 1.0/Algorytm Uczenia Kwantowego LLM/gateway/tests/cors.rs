@@ -0,0 +1,122 @@
+use std::sync::Arc;
+
+use axum_test_helper::TestClient;
+use gateway::cors::CorsConfig;
+use gateway::{build_app, AppState};
+
+fn test_state() -> Arc<AppState> {
+    Arc::new(AppState {
+        backend_base: "http://127.0.0.1:1".to_string(),
+        client: reqwest::Client::new(),
+        max_body_bytes: 1024,
+        auth: Arc::new(gateway::auth::PassthroughAuth),
+        retry: gateway::resilience::RetryConfig::from_env(),
+        request_deadline: std::time::Duration::from_secs(5),
+        circuit_breaker: Arc::new(gateway::resilience::CircuitBreaker::from_env()),
+    })
+}
+
+fn test_cors_config() -> CorsConfig {
+    CorsConfig {
+        allowed_origins: vec!["https://ase-bot.live".to_string(), "*.ase-bot.dev".to_string()],
+        allowed_headers: vec!["authorization".to_string(), "content-type".to_string()],
+        allow_credentials: true,
+        max_age: std::time::Duration::from_secs(300),
+    }
+}
+
+#[tokio::test]
+async fn allowed_origin_gets_cors_headers() {
+    let app = build_app(test_state(), &test_cors_config());
+    let client = TestClient::new(app).await;
+
+    let res = client
+        .get("/health")
+        .header("origin", "https://ase-bot.live")
+        .send()
+        .await;
+
+    assert_eq!(res.status(), http::StatusCode::OK);
+    assert_eq!(
+        res.headers().get("access-control-allow-origin").unwrap(),
+        "https://ase-bot.live"
+    );
+}
+
+#[tokio::test]
+async fn wildcard_subdomain_is_allowed() {
+    let app = build_app(test_state(), &test_cors_config());
+    let client = TestClient::new(app).await;
+
+    let res = client
+        .get("/health")
+        .header("origin", "https://staging.ase-bot.dev")
+        .send()
+        .await;
+
+    assert_eq!(
+        res.headers().get("access-control-allow-origin").unwrap(),
+        "https://staging.ase-bot.dev"
+    );
+}
+
+#[tokio::test]
+async fn rejected_origin_gets_no_cors_headers() {
+    let app = build_app(test_state(), &test_cors_config());
+    let client = TestClient::new(app).await;
+
+    let res = client
+        .get("/health")
+        .header("origin", "https://evil.example")
+        .send()
+        .await;
+
+    assert_eq!(res.status(), http::StatusCode::OK);
+    assert!(res.headers().get("access-control-allow-origin").is_none());
+}
+
+#[tokio::test]
+async fn preflight_options_reports_allowed_methods_and_max_age() {
+    let app = build_app(test_state(), &test_cors_config());
+    let client = TestClient::new(app).await;
+    let base = client.base_url();
+
+    let res = reqwest::Client::new()
+        .request(reqwest::Method::OPTIONS, format!("{base}/health"))
+        .header("origin", "https://ase-bot.live")
+        .header("access-control-request-method", "GET")
+        .send()
+        .await
+        .unwrap();
+
+    assert!(res.status().is_success());
+    assert_eq!(
+        res.headers().get("access-control-allow-origin").unwrap(),
+        "https://ase-bot.live"
+    );
+    assert_eq!(res.headers().get("access-control-max-age").unwrap(), "300");
+    let allow_methods = res
+        .headers()
+        .get("access-control-allow-methods")
+        .unwrap()
+        .to_str()
+        .unwrap();
+    assert!(allow_methods.contains("GET"));
+}
+
+#[tokio::test]
+async fn credentialed_request_sets_allow_credentials() {
+    let app = build_app(test_state(), &test_cors_config());
+    let client = TestClient::new(app).await;
+
+    let res = client
+        .get("/health")
+        .header("origin", "https://ase-bot.live")
+        .send()
+        .await;
+
+    assert_eq!(
+        res.headers().get("access-control-allow-credentials").unwrap(),
+        "true"
+    );
+}
@@ -0,0 +1,120 @@
+use std::sync::Arc;
+
+use axum::http::HeaderMap;
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::Router;
+use axum_test_helper::TestClient;
+use gateway::cors::CorsConfig;
+use gateway::{build_app, AppState};
+
+fn permissive_cors() -> CorsConfig {
+    CorsConfig {
+        allowed_origins: vec!["*.test".to_string()],
+        allowed_headers: vec!["content-type".to_string()],
+        allow_credentials: false,
+        max_age: std::time::Duration::from_secs(1),
+    }
+}
+
+/// A backend that echoes the request headers it received back as JSON, so
+/// the gateway's forwarding behavior can be inspected from the response.
+async fn spawn_echo_backend() -> String {
+    async fn echo(headers: HeaderMap) -> impl IntoResponse {
+        let mut body = String::from("{");
+        for (i, (name, value)) in headers.iter().enumerate() {
+            if i > 0 {
+                body.push(',');
+            }
+            body.push_str(&format!(
+                "\"{}\":\"{}\"",
+                name.as_str(),
+                value.to_str().unwrap_or("")
+            ));
+        }
+        body.push('}');
+        ([("content-type", "application/json")], body)
+    }
+
+    let app = Router::new().route("/*path", get(echo));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{addr}")
+}
+
+fn state_for(backend_base: String) -> Arc<AppState> {
+    Arc::new(AppState {
+        backend_base,
+        client: reqwest::Client::new(),
+        max_body_bytes: 1024,
+        auth: Arc::new(gateway::auth::PassthroughAuth),
+        retry: gateway::resilience::RetryConfig::from_env(),
+        request_deadline: std::time::Duration::from_secs(5),
+        circuit_breaker: Arc::new(gateway::resilience::CircuitBreaker::from_env()),
+    })
+}
+
+#[tokio::test]
+async fn forwards_synthesized_proxy_headers() {
+    let backend = spawn_echo_backend().await;
+    let state = state_for(backend);
+    let app = build_app(state, &permissive_cors());
+    let client = TestClient::new(app).await;
+
+    let res = client
+        .get("/anything")
+        .header("host", "ase-bot.live")
+        .send()
+        .await;
+
+    assert_eq!(res.status(), http::StatusCode::OK);
+    let body: serde_json::Value = res.json().await;
+    // The test harness doesn't wire up connect info, so the fallback
+    // unspecified address is what gets forwarded here.
+    assert_eq!(body["x-forwarded-for"], "0.0.0.0");
+    assert_eq!(body["x-forwarded-proto"], "http");
+    assert_eq!(body["x-forwarded-host"], "ase-bot.live");
+    assert_eq!(body["via"], "1.1 ase-bot-gateway");
+}
+
+#[tokio::test]
+async fn outbound_host_header_matches_backend_not_client() {
+    let backend = spawn_echo_backend().await;
+    let backend_authority = backend.trim_start_matches("http://").to_string();
+    let state = state_for(backend);
+    let app = build_app(state, &permissive_cors());
+    let client = TestClient::new(app).await;
+
+    let res = client
+        .get("/anything")
+        .header("host", "ase-bot.live")
+        .send()
+        .await;
+
+    let body: serde_json::Value = res.json().await;
+    // The outbound `Host` must be the backend's own authority, never the
+    // client-supplied one, or a malicious client could steer a backend that
+    // does Host-based routing through a trusted gateway.
+    assert_eq!(body["host"], backend_authority);
+    assert_eq!(body["x-forwarded-host"], "ase-bot.live");
+}
+
+#[tokio::test]
+async fn does_not_relay_client_supplied_auth_subject() {
+    let backend = spawn_echo_backend().await;
+    let state = state_for(backend);
+    let app = build_app(state, &permissive_cors());
+    let client = TestClient::new(app).await;
+
+    let res = client
+        .get("/anything")
+        .header("x-auth-subject", "attacker-supplied")
+        .send()
+        .await;
+
+    let body: serde_json::Value = res.json().await;
+    assert_eq!(body["x-auth-subject"], "anonymous");
+}
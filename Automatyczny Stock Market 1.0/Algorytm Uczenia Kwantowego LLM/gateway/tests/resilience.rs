@@ -0,0 +1,79 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum_test_helper::TestClient;
+use gateway::cors::CorsConfig;
+use gateway::resilience::{CircuitBreaker, RetryConfig};
+use gateway::{build_app, AppState};
+
+fn state_for(backend_base: String, deadline: Duration, cb_threshold: u32) -> Arc<AppState> {
+    // Built with `CircuitBreaker::new` rather than `from_env` +
+    // env::set_var, since cargo runs tests in this binary concurrently and
+    // process-global env vars would race across tests.
+    let circuit_breaker = Arc::new(CircuitBreaker::new(cb_threshold, Duration::from_secs(60)));
+
+    Arc::new(AppState {
+        backend_base,
+        client: reqwest::Client::new(),
+        max_body_bytes: 1024,
+        auth: Arc::new(gateway::auth::PassthroughAuth),
+        retry: RetryConfig::from_env(),
+        request_deadline: deadline,
+        circuit_breaker,
+    })
+}
+
+fn permissive_cors() -> CorsConfig {
+    CorsConfig {
+        allowed_origins: vec!["*.test".to_string()],
+        allowed_headers: vec!["content-type".to_string()],
+        allow_credentials: false,
+        max_age: Duration::from_secs(1),
+    }
+}
+
+/// A backend that accepts the connection but never writes a response,
+/// forcing the gateway's deadline to fire.
+async fn spawn_stalling_backend() -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        loop {
+            if let Ok((socket, _)) = listener.accept().await {
+                // Hold the connection open without ever responding.
+                std::mem::forget(socket);
+            }
+        }
+    });
+    format!("http://{addr}")
+}
+
+#[tokio::test]
+async fn slow_backend_yields_request_timeout() {
+    let backend = spawn_stalling_backend().await;
+    let state = state_for(backend, Duration::from_millis(200), 100);
+    let app = build_app(state, &permissive_cors());
+    let client = TestClient::new(app).await;
+
+    let res = client.get("/anything").send().await;
+    assert_eq!(res.status(), http::StatusCode::REQUEST_TIMEOUT);
+}
+
+#[tokio::test]
+async fn unreachable_backend_opens_circuit_breaker() {
+    // Nothing listens on this port: every attempt is a connection error.
+    let backend = "http://127.0.0.1:1".to_string();
+    let state = state_for(backend, Duration::from_secs(2), 2);
+    let app = build_app(state, &permissive_cors());
+    let client = TestClient::new(app).await;
+
+    // GET is retried internally (idempotent, no body), so the first call
+    // alone racks up enough consecutive failures to cross the threshold of 2.
+    let first = client.get("/anything").send().await;
+    assert_eq!(first.status(), http::StatusCode::BAD_GATEWAY);
+
+    // Breaker is now open: the next call is short-circuited before it ever
+    // touches the (unreachable) backend.
+    let second = client.get("/anything").send().await;
+    assert_eq!(second.status(), http::StatusCode::SERVICE_UNAVAILABLE);
+}